@@ -1,406 +1,920 @@
-/// 全局的线程安全的原子字符串池，减少相同字符串的内存占用，也用于hashmap的键
-/// 如果全局该字符串最后一个引用被释放， 则该字符串会释放。
-/// 为了减少不停的创建和放入池的次数，高频单次的Atom，可以在应用层增加一个cache来缓冲Atom，定期检查引用计数来判断是否缓冲。
-
-#[macro_use]
-extern crate lazy_static;
-#[cfg(feature = "serde")]
-#[macro_use]
-extern crate serde;
-
-use core::fmt;
-use std::borrow::{Borrow, Cow};
-use std::convert::Infallible;
-use std::hash::{Hash, Hasher};
-use std::iter::*;
-use std::ops::Deref;
-use std::str::FromStr;
-
-use pi_bon::{WriteBuffer, ReadBuffer, Encode, Decode, ReadBonErr};
-use dashmap::DashMap;
-use pi_share::{Share, ShareWeak};
-
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use smol_str::SmolStr;
-
-// 同步原语，可用于运行一次性初始化。用于全局，FFI或相关功能的一次初始化。
-lazy_static! {
-    static ref ATOM_MAP: DashMap<SmolStr, Share<(SmolStr, Usize)>> = DashMap::default();
-    static ref HASH_MAP: DashMap<Usize, ShareWeak<(SmolStr, Usize)>> = DashMap::default();
-    pub static ref EMPTY: Atom = Atom::from("");
-}
-
-#[cfg(all(not(feature = "pi_hash/xxhash"), not(feature = "pointer_width_32")))]
-pub type CurHasher = fxhash::FxHasher64;
-
-#[cfg(all(not(feature = "pi_hash/xxhash"), feature = "pointer_width_32"))]
-pub type CurHasher = fxhash::FxHasher32;
-
-#[cfg(all(feature = "pi_hash/xxhash", not(feature = "pointer_width_32")))]
-pub type CurHasher = twox_hash::XxHash64;
-
-#[cfg(all(feature = "pi_hash/xxhash", feature = "pointer_width_32"))]
-pub type CurHasher = twox_hash::XxHash32;
-
-#[cfg(feature = "pointer_width_32")]
-pub type Usize = u32;
-#[cfg(not(feature = "pointer_width_32"))]
-pub type Usize = u64;
-
-#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
-pub struct Atom(Share<(SmolStr, Usize)>);
-unsafe impl Sync for Atom {}
-unsafe impl Send for Atom {}
-
-impl Encode for Atom{
-    fn encode(&self, bb: &mut WriteBuffer){
-        (*self.0).0.as_str().to_string().encode(bb);
-    }
-}
-
-impl Decode for Atom{
-    fn decode(bb: &mut ReadBuffer) -> Result<Atom, ReadBonErr>{
-        Ok(Atom::from(String::decode(bb)?))
-    }
-}
-
-impl Atom {
-    pub fn new<T>(text: T) -> Self
-    where
-        T: AsRef<str>,
-    {
-        Self::create(SmolStr::new(text))
-    }
-    pub fn create(s: SmolStr) -> Atom {
-        match ATOM_MAP.entry(s) {
-            dashmap::mapref::entry::Entry::Occupied(entry) => Atom(entry.get().clone()),
-            dashmap::mapref::entry::Entry::Vacant(entry) => {
-                let s = entry.key().clone();
-                let str_hash = str_hash(&s);
-                let r = Share::new((s, str_hash));
-                entry.insert(r.clone());
-                #[cfg(feature="lookup_by_hash")]
-                {
-                    HASH_MAP.insert(str_hash, Share::downgrade(&r));
-                }
-                Atom(r)
-            }
-        }
-    }
-
-    #[inline(always)]
-    pub fn as_str(&self) -> &str {
-        self.0 .0.as_str()
-    }
-    /// 获取该Atom的hash值
-    #[inline(always)]
-    pub fn str_hash(&self) -> Usize {
-        self.0 .1
-    }
-}
-
-impl Hash for Atom {
-    fn hash<H: Hasher>(&self, h: &mut H) {
-        #[cfg(feature = "pointer_width_32")]
-        h.write_u32(self.0 .1);
-        #[cfg(not(feature = "pointer_width_32"))]
-        h.write_u64(self.0 .1)
-    }
-}
-impl Drop for Atom {
-    fn drop(&mut self) {
-        if Share::<(SmolStr, Usize)>::strong_count(&self.0) > 2 {
-            return;
-        }
-        ATOM_MAP.remove_if(&(self.0).0, |_, _| {
-            // 进入锁后，再次判断是否需要释放
-            if Share::<(SmolStr, Usize)>::strong_count(&self.0) > 2 {
-                return false;
-            }
-            #[cfg(feature="lookup_by_hash")]
-            {
-                HASH_MAP.remove(&self.0.1);
-            }    
-            true
-        });
-    }
-}
-
-impl Deref for Atom {
-    type Target = str;
-
-    fn deref(&self) -> &str {
-        (self.0).0.as_str()
-    }
-}
-
-impl AsRef<str> for Atom {
-    #[inline(always)]
-    fn as_ref(&self) -> &str {
-        self.as_str()
-    }
-}
-
-impl fmt::Display for Atom {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(self.as_str(), f)
-    }
-}
-
-impl FromIterator<char> for Atom {
-    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Atom {
-        Self::create(SmolStr::from_iter(iter))
-    }
-}
-
-impl FromIterator<String> for Atom {
-    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Atom {
-        Self::create(SmolStr::from_iter(iter))
-    }
-}
-
-impl<'a> FromIterator<&'a String> for Atom {
-    fn from_iter<I: IntoIterator<Item = &'a String>>(iter: I) -> Atom {
-        Self::create(SmolStr::from_iter(iter))
-    }
-}
-
-impl<'a> FromIterator<&'a str> for Atom {
-    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Atom {
-        Self::create(SmolStr::from_iter(iter))
-    }
-}
-
-impl From<&str> for Atom {
-    #[inline]
-    fn from(s: &str) -> Atom {
-        Atom::new(s)
-    }
-}
-
-impl From<&mut str> for Atom {
-    #[inline]
-    fn from(s: &mut str) -> Atom {
-        Atom::new(s)
-    }
-}
-
-impl From<&String> for Atom {
-    #[inline]
-    fn from(s: &String) -> Atom {
-        Atom::new(s)
-    }
-}
-
-impl From<String> for Atom {
-    #[inline(always)]
-    fn from(text: String) -> Self {
-        Self::new(text)
-    }
-}
-
-impl From<Box<str>> for Atom {
-    #[inline]
-    fn from(s: Box<str>) -> Atom {
-        Atom::new(s)
-    }
-}
-
-impl<'a> From<Cow<'a, str>> for Atom {
-    #[inline]
-    fn from(s: Cow<'a, str>) -> Atom {
-        Atom::new(s)
-    }
-}
-impl<'a> From<&'a [u8]> for Atom {
-    #[inline(always)]
-    fn from(s: &[u8]) -> Atom {
-        Atom::new(core::str::from_utf8(s).unwrap())
-    }
-}
-
-impl From<Atom> for String {
-    #[inline(always)]
-    fn from(text: Atom) -> Self {
-        text.as_str().into()
-    }
-}
-
-impl Borrow<str> for Atom {
-    #[inline(always)]
-    fn borrow(&self) -> &str {
-        self.as_str()
-    }
-}
-
-impl FromStr for Atom {
-    type Err = Infallible;
-
-    #[inline]
-    fn from_str(s: &str) -> Result<Atom, Self::Err> {
-        Ok(Atom::from(s))
-    }
-}
-
-#[cfg(feature = "serde")]
-impl Serialize for Atom {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        self.as_str().serialize(serializer)
-    }
-}
-#[cfg(feature = "serde")]
-impl<'de> Deserialize<'de> for Atom {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        Ok(Self::create(SmolStr::deserialize(deserializer)?))
-    }
-}
-
-#[inline(always)]
-pub fn str_hash<R: AsRef<str>>(s: R) -> Usize {
-    let hasher = &mut CurHasher::default();
-    s.as_ref().hash(hasher);
-    hasher.finish() as Usize
-}
-
-#[inline(always)]
-pub fn get_by_hash(hash: Usize) -> Option<Atom> {
-    HASH_MAP
-        .get(&hash)
-        .map_or(None, |r| r.value().upgrade().map(|r| Atom(r)))
-}
-#[inline(always)]
-pub fn store_weak_by_hash(atom: Atom) {
-    HASH_MAP.insert(atom.0 .1, Share::<(SmolStr, Usize)>::downgrade(&atom.0));
-}
-#[inline(always)]
-pub fn collect() {
-    HASH_MAP.retain(|_, v| v.strong_count() > 0);
-}
-
-#[cfg(test)]
-mod tests {
-    //use std::{time::Duration, thread};
-
-
-    use crate::*;
-    use pi_hash::XHashMap;
-
-    #[test]
-    fn test_atom1() {
-        let at3 = Atom::from("RES_GLTF_ACCESSOR_BUFFER_VIEW:app/scene_res/res/u3d_anim/eff_sz_chouka_daiji/eff_sz_chouka_daiji.gltf#Indices#19");
-        let at4 = Atom::from("RES_GLTF_ACCESSOR_BUFFER_VIEW:app/scene_res/res/u3d_anim/eff_sz_chouka_daiji/eff_sz_chouka_daiji.gltf#Indices#34");
-        println!("at3:{:?}, at4:{:?}", at3.str_hash(), at4.str_hash())
-    }
-
-    #[test]
-    fn test_atom() {
-        let at3 = Atom::from("afg");
-        assert_eq!(at3.as_str(), "afg");
-
-        let mut map = XHashMap::default();
-        let time = std::time::Instant::now();
-        for i in 0..1000000 {
-            map.insert(i.to_string(), i);
-        }
-        println!("insert map time:{:?}", std::time::Instant::now() - time);
-
-        let time = std::time::Instant::now();
-        let mut vec1 = vec![];
-        for i in 0..1000000 {
-            vec1.push(Atom::from(i.to_string()));
-        }
-        println!("atom from time:{:?}", std::time::Instant::now() - time);
-
-        let time = std::time::Instant::now();
-        let mut vec2 = vec![];
-        for i in 0..1000000 {
-            vec2.push(Atom::from(i.to_string()));
-        }
-        println!("atom look time:{:?}", std::time::Instant::now() - time);
-
-        let mut arr3 = Vec::new();
-        for i in 0..1000 {
-            arr3.push(Atom::from(i.to_string()));
-        }
-        let mut arr4 = Vec::new();
-        let time = std::time::Instant::now();
-        for i in 0..1000 {
-            for _ in 0..1000 {
-                arr4.push(Atom::from(arr3[i].as_str()));
-            }
-        }
-        println!("atom1 from time:{:?}", std::time::Instant::now() - time);
-        let mut arr5 = Vec::new();
-        let time = std::time::Instant::now();
-        for i in 0..1000 {
-            for _ in 0..1000 {
-                arr5.push(Share::new((arr3[i].as_str().to_string(), 5)));
-            }
-        }
-        println!("Share::new time:{:?}", std::time::Instant::now() - time);
-
-        let time = std::time::Instant::now();
-        for i in 0..1000 {
-            for _ in 0..1000 {
-                let _ = arr3[i].as_str();
-            }
-        }
-        println!("to_str time:{:?}", std::time::Instant::now() - time);
-
-        let time = std::time::Instant::now();
-        let xx = Share::new(1);
-        let w = Share::downgrade(&xx);
-        for _ in 0..1000000 {
-            let _ = w.upgrade();
-        }
-        println!("upgrade:{:?}", std::time::Instant::now() - time);
-
-        let time = std::time::Instant::now();
-        let xx = Share::new(1);
-        //let w = Share::downgrade(&xx);
-        for _ in 0..1000 {
-            for _ in 0..1000 {
-                let _a = xx.clone();
-            }
-        }
-        println!("clone: {:?}", std::time::Instant::now() - time);
-    }
-    #[test]
-    fn test_rng() {
-        let _thread = std::thread::spawn(|| {
-            rng();
-            return;
-        });
-
-        // thread.join().unwrap();
-
-        rng();
-        return;
-    }
-    fn rng() {
-        let mut vec = vec![];
-        for _ in 0..1000000 {
-            //thread::sleep(Duration::from_millis(0));
-            let mut buf = [0u8; 4];
-            getrandom::getrandom(&mut buf).unwrap();
-            let r = unsafe { *(buf.as_ptr() as usize as *mut u32) };
-            if r % 4 == 0 {
-                vec.push(Atom::from(r.to_string()));
-            } else if r % 4 == 1 && vec.len() > 0 {
-                let c = vec[r as usize % vec.len()].clone();
-                vec.push(c);
-            } else {
-                if vec.len() > 0 {
-                    vec.swap_remove(r as usize % vec.len());
-                }
-            }
-        }
-    }
-}
+/// 全局的线程安全的原子字符串池，减少相同字符串的内存占用，也用于hashmap的键
+/// 如果全局该字符串最后一个引用被释放， 则该字符串会释放。
+/// 为了减少不停的创建和放入池的次数，高频单次的Atom，可以在应用层增加一个cache来缓冲Atom，定期检查引用计数来判断是否缓冲。
+///
+/// `Atom` 内部是一个带标签的联合体（tagged union），而不是单一的池化表示：
+/// - `Inline`：长度不超过 `INLINE_CAP` 字节的短字符串，直接存在 `Atom` 内部，不占用池、不需要原子引用计数；
+/// - `Static`：指向一张进程内静态表中的一项（见 `atom!` 宏），同样不占用 `ATOM_MAP`；
+/// - `Dynamic`：今天这种 `Share<(SmolStr, Usize)>` 池化表示，用于其余的字符串。
+/// 三种变体共享同一套 `as_str`/`Hash`/`Eq`/`Deref` 行为，对调用者完全透明。
+
+#[macro_use]
+extern crate lazy_static;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+use core::fmt;
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
+use std::convert::Infallible;
+use std::hash::{Hash, Hasher};
+use std::iter::*;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use pi_bon::{WriteBuffer, ReadBuffer, Encode, Decode, ReadBonErr};
+use dashmap::DashMap;
+use pi_share::{Share, ShareWeak};
+use smallvec::SmallVec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use smol_str::SmolStr;
+
+// 同步原语，可用于运行一次性初始化。用于全局，FFI或相关功能的一次初始化。
+lazy_static! {
+    static ref ATOM_MAP: DashMap<SmolStr, Share<(SmolStr, Usize)>> = DashMap::default();
+    // 一个 Usize 哈希可能被多个不同的字符串共享（生日悖论下并不罕见），
+    // 所以每个哈希对应一个小桶，而不是单个弱引用；真正命中哪一个，
+    // 要在取出时用字符串本身校验。
+    static ref HASH_MAP: DashMap<Usize, SmallVec<[ShareWeak<(SmolStr, Usize)>; 1]>> = DashMap::default();
+    pub static ref EMPTY: Atom = Atom::from("");
+}
+
+#[cfg(all(not(feature = "pi_hash/xxhash"), not(feature = "pointer_width_32")))]
+pub type CurHasher = fxhash::FxHasher64;
+
+#[cfg(all(not(feature = "pi_hash/xxhash"), feature = "pointer_width_32"))]
+pub type CurHasher = fxhash::FxHasher32;
+
+#[cfg(all(feature = "pi_hash/xxhash", not(feature = "pointer_width_32")))]
+pub type CurHasher = twox_hash::XxHash64;
+
+#[cfg(all(feature = "pi_hash/xxhash", feature = "pointer_width_32"))]
+pub type CurHasher = twox_hash::XxHash32;
+
+#[cfg(feature = "pointer_width_32")]
+pub type Usize = u32;
+#[cfg(not(feature = "pointer_width_32"))]
+pub type Usize = u64;
+
+/// 内联短字符串能够容纳的最大字节数。
+/// 选 7 是为了让 `Inline` 变体和一个带标签的机器字（tag + 载荷）大小相当。
+pub const INLINE_CAP: usize = 7;
+
+/// 不经过池、直接以值的形式存放在 `Atom` 内部的短字符串。
+#[derive(Clone, Copy)]
+struct Inline {
+    len: u8,
+    bytes: [u8; INLINE_CAP],
+}
+
+impl Inline {
+    /// 字符串的字节长度不超过 `INLINE_CAP` 时返回 `Some`，否则返回 `None`。
+    #[inline(always)]
+    fn new(s: &str) -> Option<Self> {
+        let b = s.as_bytes();
+        if b.len() > INLINE_CAP {
+            return None;
+        }
+        let mut bytes = [0u8; INLINE_CAP];
+        bytes[..b.len()].copy_from_slice(b);
+        Some(Inline {
+            len: b.len() as u8,
+            bytes,
+        })
+    }
+
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        // 构造时已校验来自合法的 &str，切片仍然是合法的 utf8 边界
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[..self.len as usize]) }
+    }
+}
+
+/// 声明一组常用字符串为静态原子。新增条目直接加入这个列表即可。
+/// 首次访问任意 Atom 接口时，会据此惰性构建一张完美哈希表（见 `StaticPhf`），
+/// 之后 `hash -> 下标` 的探测是 O(1) 且零碰撞的，不需要遍历或加锁。
+static STATIC_ATOM_STRS: &[&str] = &[
+    "className", "children", "viewBox", "transform", "translate", "rotateZ",
+    "background", "undefined", "function", "position",
+];
+
+lazy_static! {
+    /// 静态原子表：字符串及其预计算哈希，下标即 `Repr::Static` 中保存的值。
+    static ref STATIC_ATOMS: Vec<(&'static str, Usize)> =
+        STATIC_ATOM_STRS.iter().map(|s| (*s, str_hash(s))).collect();
+    /// `STATIC_ATOMS` 对应的完美哈希表，用于把一个任意字符串的哈希 O(1) 地
+    /// 映射到它在 `STATIC_ATOMS` 中的下标（如果它确实是一个静态原子的话）。
+    static ref STATIC_PHF: StaticPhf =
+        StaticPhf::build(&STATIC_ATOMS.iter().map(|(_, h)| *h).collect::<Vec<_>>());
+}
+
+/// 借用 `string_cache`/`phf` 的思路构建的一张最小完美哈希表：
+/// 按哈希值把键分到 `n` 个桶里，优先处理冲突最多的桶，为每个桶寻找一个
+/// 位移量，使桶内所有键都能落在当前还空着的槽位上。构建只发生一次，
+/// 构建完成后查表只需计算一次桶下标、一次位移哈希，不会再发生碰撞重试。
+struct StaticPhf {
+    /// 按桶下标存放的位移量
+    disps: Vec<u32>,
+    /// 最终槽位 -> 原始键下标；`u32::MAX` 表示空槽
+    map: Vec<u32>,
+}
+
+impl StaticPhf {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    fn build(hashes: &[Usize]) -> StaticPhf {
+        let n = hashes.len();
+        if n == 0 {
+            return StaticPhf { disps: Vec::new(), map: Vec::new() };
+        }
+
+        let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); n];
+        for (i, &h) in hashes.iter().enumerate() {
+            buckets[h as usize % n].push(i as u32);
+        }
+        // 先处理碰撞多的桶，更容易为剩下的桶找到可用位移
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&b| std::cmp::Reverse(buckets[b].len()));
+
+        let mut map: Vec<Option<u32>> = vec![None; n];
+        let mut disps: Vec<u32> = vec![0; n];
+        for &b in &order {
+            let items = &buckets[b];
+            if items.is_empty() {
+                continue;
+            }
+            let mut d: u32 = 0;
+            loop {
+                let mut slots = Vec::with_capacity(items.len());
+                let mut ok = true;
+                for &idx in items {
+                    let slot = Self::displace(hashes[idx as usize], d, n);
+                    if map[slot].is_some() || slots.contains(&slot) {
+                        ok = false;
+                        break;
+                    }
+                    slots.push(slot);
+                }
+                if ok {
+                    for (slot, &idx) in slots.iter().zip(items.iter()) {
+                        map[*slot] = Some(idx);
+                    }
+                    disps[b] = d;
+                    break;
+                }
+                d += 1;
+            }
+        }
+        StaticPhf {
+            disps,
+            map: map.into_iter().map(|v| v.unwrap_or(u32::MAX)).collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn displace(hash: Usize, d: u32, n: usize) -> usize {
+        let mixed = (hash as u64) ^ (d as u64).wrapping_mul(Self::SEED);
+        mixed as usize % n
+    }
+
+    /// 返回哈希对应的候选下标；调用方仍需用原字符串校验，因为这是一张
+    /// "对已知键集合无碰撞" 的表，查询未注册过的字符串时可能返回误命中的下标。
+    #[inline(always)]
+    fn get(&self, hash: Usize) -> Option<u32> {
+        if self.disps.is_empty() {
+            return None;
+        }
+        let n = self.disps.len();
+        let bucket = hash as usize % n;
+        let slot = Self::displace(hash, self.disps[bucket], n);
+        match self.map[slot] {
+            u32::MAX => None,
+            idx => Some(idx),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Repr {
+    /// 不超过 `INLINE_CAP` 字节的短字符串，直接内联存储，没有池开销
+    Inline(Inline),
+    /// `STATIC_ATOMS` 中的下标
+    Static(u32),
+    /// 今天的池化表示：`ATOM_MAP` 中唯一的一份 `Share`
+    Dynamic(Share<(SmolStr, Usize)>),
+}
+
+#[derive(Clone)]
+pub struct Atom(Repr);
+unsafe impl Sync for Atom {}
+unsafe impl Send for Atom {}
+
+impl Default for Atom {
+    #[inline]
+    fn default() -> Self {
+        Atom(Repr::Inline(Inline::new("").unwrap()))
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Atom").field(&self.as_str()).finish()
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            // 池中每个不同的字符串只有唯一一份 Share，指针相同即内容相同，
+            // 不需要再比较字符串本身，O(1) 而与长度无关
+            (Repr::Dynamic(a), Repr::Dynamic(b)) => {
+                Share::<(SmolStr, Usize)>::ptr_eq(a, b)
+            }
+            // 静态原子由下标唯一标识同一张表里的同一项
+            (Repr::Static(a), Repr::Static(b)) => a == b,
+            // 内联字符串本身就是值，按字节比较
+            (Repr::Inline(a), Repr::Inline(b)) => a.len == b.len && a.bytes == b.bytes,
+            // 跨变体比较时，指针/下标没有意义，只能退回内容比较
+            _ => self.as_str() == other.as_str(),
+        }
+    }
+}
+impl Eq for Atom {}
+
+impl PartialOrd for Atom {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Atom {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Encode for Atom{
+    fn encode(&self, bb: &mut WriteBuffer){
+        self.as_str().to_string().encode(bb);
+    }
+}
+
+impl Decode for Atom{
+    fn decode(bb: &mut ReadBuffer) -> Result<Atom, ReadBonErr>{
+        Ok(Atom::from(String::decode(bb)?))
+    }
+}
+
+impl Atom {
+    pub fn new<T>(text: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self::create(SmolStr::new(text))
+    }
+
+    pub fn create(s: SmolStr) -> Atom {
+        // 静态表优先于内联检查：否则任何长度不超过 INLINE_CAP 的已登记
+        // 静态原子（例如 7 字节的 "viewBox"）会一直被内联路径抢先命中，
+        // 永远无法解析成 `Repr::Static`。
+        if let Some(atom) = Self::static_atom(s.as_str()) {
+            return atom;
+        }
+        if let Some(inline) = Inline::new(s.as_str()) {
+            return Atom(Repr::Inline(inline));
+        }
+        match ATOM_MAP.entry(s) {
+            dashmap::mapref::entry::Entry::Occupied(entry) => Atom(Repr::Dynamic(entry.get().clone())),
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                let s = entry.key().clone();
+                let str_hash = str_hash(&s);
+                let r = Share::new((s, str_hash));
+                entry.insert(r.clone());
+                #[cfg(feature="lookup_by_hash")]
+                {
+                    HASH_MAP
+                        .entry(str_hash)
+                        .or_insert_with(SmallVec::new)
+                        .push(Share::downgrade(&r));
+                }
+                Atom(Repr::Dynamic(r))
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Inline(s) => s.as_str(),
+            Repr::Static(idx) => STATIC_ATOMS[*idx as usize].0,
+            Repr::Dynamic(r) => r.0.as_str(),
+        }
+    }
+
+    /// 获取该Atom的hash值
+    #[inline(always)]
+    pub fn str_hash(&self) -> Usize {
+        match &self.0 {
+            Repr::Inline(s) => str_hash(s.as_str()),
+            Repr::Static(idx) => STATIC_ATOMS[*idx as usize].1,
+            Repr::Dynamic(r) => r.1,
+        }
+    }
+
+    /// 在静态原子表（`STATIC_ATOM_STRS`）中查找该字符串，命中则直接返回
+    /// `Static` 变体，不经过 `ATOM_MAP`。
+    #[inline]
+    fn static_atom(s: &str) -> Option<Atom> {
+        let hash = str_hash(s);
+        let idx = STATIC_PHF.get(hash)?;
+        if STATIC_ATOMS[idx as usize].0 == s {
+            Some(Atom(Repr::Static(idx)))
+        } else {
+            None
+        }
+    }
+
+    /// 该 Atom 是否命中了静态原子表。主要给 `atom!` 宏在 debug 模式下自检用。
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        matches!(self.0, Repr::Static(_))
+    }
+
+    /// 批量创建/查找 Atom，一次性走完整个输入序列。
+    pub fn intern_many<I>(iter: I) -> Vec<Atom>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        iter.into_iter().map(Atom::new).collect()
+    }
+
+    /// 当前 `ATOM_MAP`（动态池）中的字符串数量，不含内联/静态原子。
+    #[inline]
+    pub fn pool_len() -> usize {
+        ATOM_MAP.len()
+    }
+}
+
+/// 将一个字符串字面量解析为 `Atom`。若该字面量已经登记在 `STATIC_ATOM_STRS`
+/// 中，得到的 `Atom` 会直接复用静态原子表中的那一项，不经过 `ATOM_MAP`；
+/// 否则在 release 模式下退化为普通的 `Atom::new`，在 debug 模式下
+/// `debug_assert!` 失败并 panic，指出该字面量未登记。
+///
+/// 注意：这里没有 `build.rs`/过程宏生成的编译期常量表，`STATIC_PHF` 是在
+/// 首次用到任何 Atom 接口时惰性构建一次的，`atom!` 宏本身只是
+/// `Atom::new` 外面套了一层 debug 自检，命中静态表时仍然要付一次运行期
+/// 的 `str_hash` + 完美哈希探测（O(1)，但不是零开销），和直接写
+/// `Atom::from($s)` 成本相同。
+#[macro_export]
+macro_rules! atom {
+    ($s:literal) => {{
+        let atom = $crate::Atom::new($s);
+        debug_assert!(
+            $crate::Atom::is_static(&atom),
+            "atom!({:?}) is not registered in STATIC_ATOM_STRS",
+            $s
+        );
+        atom
+    }};
+}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        let hash = self.str_hash();
+        #[cfg(feature = "pointer_width_32")]
+        h.write_u32(hash);
+        #[cfg(not(feature = "pointer_width_32"))]
+        h.write_u64(hash)
+    }
+}
+/// 检查 `r` 是否仍然只剩 `ATOM_MAP` 自己持有的一份引用，是的话把它从
+/// `ATOM_MAP`/`HASH_MAP` 中摘除。这一步本身在 `ATOM_MAP` 的分片锁内完成，
+/// 所以即便在调用前 `strong_count` 看起来已经可以回收，只要在拿到锁之后
+/// 发现被并发的 `create` 复活（强引用计数又涨回去了），就不会误删。
+/// 内联回收（关闭 `epoch_gc` 时）和批量回收（`gc()`）都复用这同一个函数。
+fn reclaim_if_dead(r: &Share<(SmolStr, Usize)>) {
+    ATOM_MAP.remove_if(&r.0, |_, _| {
+        // 进入锁后，再次判断是否需要释放
+        if Share::<(SmolStr, Usize)>::strong_count(r) > 2 {
+            return false;
+        }
+        #[cfg(feature = "lookup_by_hash")]
+        {
+            // 只摘除这一个弱引用对应的条目，同哈希桶里其它字符串的条目不受影响
+            if let dashmap::mapref::entry::Entry::Occupied(mut entry) = HASH_MAP.entry(r.1) {
+                let bucket = entry.get_mut();
+                bucket.retain(|w| w.upgrade().map_or(true, |s| !Share::<(SmolStr, Usize)>::ptr_eq(&s, r)));
+                if bucket.is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+        true
+    });
+}
+
+impl Drop for Atom {
+    fn drop(&mut self) {
+        let r = match &self.0 {
+            Repr::Dynamic(r) => r,
+            // 内联和静态变体不占用池，无需任何回收逻辑
+            _ => return,
+        };
+        if Share::<(SmolStr, Usize)>::strong_count(r) > 2 {
+            return;
+        }
+        // 开启 epoch_gc 时，不在 Drop 里直接抢分片锁，而是把可回收的条目
+        // 挂到一个无锁队列上，交给 gc()/阈值触发的批量回收去处理，减少高频
+        // clone/drop 场景下对 ATOM_MAP 分片锁的争用。
+        //
+        // 这里用一个零开销的内联占位值把 `self.0` 换出来，取出里面那份
+        // `Share` 本身而不是再 clone 一份：`self` 马上就要析构完毕，如果
+        // 还 clone 一份塞进队列，强引用计数会变成 ATOM_MAP + self.0 + 队列
+        // = 3，`defer_reclaim` 里同步触发的 `gc()` 会把它误判为"仍被外部
+        // 持有"而直接丢弃、不再重新入队——这个条目就永远不会被回收了。
+        // 换出之后队列拿到的是唯一一份多出来的引用，计数正好是 2。
+        #[cfg(feature = "epoch_gc")]
+        {
+            if let Repr::Dynamic(r) = std::mem::replace(&mut self.0, Repr::Inline(Inline::new("").unwrap())) {
+                epoch_gc::defer_reclaim(r);
+            }
+            return;
+        }
+        #[cfg(not(feature = "epoch_gc"))]
+        {
+            reclaim_if_dead(r);
+        }
+    }
+}
+
+/// 高频 clone/drop 下的批量回收：`Atom::drop` 不再每次都去抢 `ATOM_MAP`
+/// 的分片锁，而是把候选条目攒到一个无锁队列里，累计到阈值或者调用方显式
+/// `gc()` 时才真正去分片锁下核实并摘除。
+#[cfg(feature = "epoch_gc")]
+mod epoch_gc {
+    use super::*;
+    use crossbeam_queue::SegQueue;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    lazy_static! {
+        static ref PENDING: SegQueue<Share<(SmolStr, Usize)>> = SegQueue::new();
+    }
+    static PENDING_LEN: AtomicUsize = AtomicUsize::new(0);
+    static GC_THRESHOLD: AtomicUsize = AtomicUsize::new(4096);
+
+    /// 设置挂起队列达到多长时自动触发一次 `gc()`；供调用方在内存和吞吐之间取舍。
+    pub fn set_gc_threshold(n: usize) {
+        GC_THRESHOLD.store(n.max(1), Ordering::Relaxed);
+    }
+
+    pub(crate) fn defer_reclaim(r: Share<(SmolStr, Usize)>) {
+        PENDING.push(r);
+        if PENDING_LEN.fetch_add(1, Ordering::Relaxed) + 1 >= GC_THRESHOLD.load(Ordering::Relaxed) {
+            gc();
+        }
+    }
+
+    /// 批量回收挂起队列中的条目。每一项都重新核实强引用计数——
+    /// 如果在入队之后被并发的 `create` 复活，这里会发现并跳过，不会误删。
+    pub fn gc() {
+        while let Some(r) = PENDING.pop() {
+            PENDING_LEN.fetch_sub(1, Ordering::Relaxed);
+            if Share::<(SmolStr, Usize)>::strong_count(&r) > 2 {
+                continue;
+            }
+            reclaim_if_dead(&r);
+        }
+    }
+}
+#[cfg(feature = "epoch_gc")]
+pub use epoch_gc::{gc, set_gc_threshold};
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Atom {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl FromIterator<char> for Atom {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Atom {
+        Self::create(SmolStr::from_iter(iter))
+    }
+}
+
+impl FromIterator<String> for Atom {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Atom {
+        Self::create(SmolStr::from_iter(iter))
+    }
+}
+
+impl<'a> FromIterator<&'a String> for Atom {
+    fn from_iter<I: IntoIterator<Item = &'a String>>(iter: I) -> Atom {
+        Self::create(SmolStr::from_iter(iter))
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Atom {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Atom {
+        Self::create(SmolStr::from_iter(iter))
+    }
+}
+
+impl From<&str> for Atom {
+    #[inline]
+    fn from(s: &str) -> Atom {
+        Atom::new(s)
+    }
+}
+
+impl From<&mut str> for Atom {
+    #[inline]
+    fn from(s: &mut str) -> Atom {
+        Atom::new(s)
+    }
+}
+
+impl From<&String> for Atom {
+    #[inline]
+    fn from(s: &String) -> Atom {
+        Atom::new(s)
+    }
+}
+
+impl From<String> for Atom {
+    #[inline(always)]
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<Box<str>> for Atom {
+    #[inline]
+    fn from(s: Box<str>) -> Atom {
+        Atom::new(s)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Atom {
+    #[inline]
+    fn from(s: Cow<'a, str>) -> Atom {
+        Atom::new(s)
+    }
+}
+impl<'a> From<&'a [u8]> for Atom {
+    #[inline(always)]
+    fn from(s: &[u8]) -> Atom {
+        Atom::new(core::str::from_utf8(s).unwrap())
+    }
+}
+
+impl From<Atom> for String {
+    #[inline(always)]
+    fn from(text: Atom) -> Self {
+        text.as_str().into()
+    }
+}
+
+impl Borrow<str> for Atom {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl FromStr for Atom {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Atom, Self::Err> {
+        Ok(Atom::from(s))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Atom {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Atom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::create(SmolStr::deserialize(deserializer)?))
+    }
+}
+
+#[inline(always)]
+pub fn str_hash<R: AsRef<str>>(s: R) -> Usize {
+    let hasher = &mut CurHasher::default();
+    s.as_ref().hash(hasher);
+    hasher.finish() as Usize
+}
+
+/// 返回给定哈希值当前存活的所有候选 Atom。由于一个哈希可能被多个不同的
+/// 字符串共享（碰撞），结果可能不止一个；调用方如果知道具体字符串，
+/// 应优先使用 [`get_by_hash_str`] 来精确地、无歧义地取得目标 Atom。
+#[inline]
+pub fn get_by_hash(hash: Usize) -> SmallVec<[Atom; 1]> {
+    match HASH_MAP.get(&hash) {
+        Some(bucket) => bucket
+            .value()
+            .iter()
+            .filter_map(|w| w.upgrade().map(|r| Atom(Repr::Dynamic(r))))
+            .collect(),
+        None => SmallVec::new(),
+    }
+}
+
+/// 返回给定哈希值对应、且内容与 `s` 完全一致的 Atom，解决碰撞下的歧义。
+#[inline]
+pub fn get_by_hash_str(hash: Usize, s: &str) -> Option<Atom> {
+    let bucket = HASH_MAP.get(&hash)?;
+    bucket.value().iter().find_map(|w| {
+        let r = w.upgrade()?;
+        if r.0.as_str() == s {
+            Some(Atom(Repr::Dynamic(r)))
+        } else {
+            None
+        }
+    })
+}
+
+#[inline(always)]
+pub fn store_weak_by_hash(atom: Atom) {
+    if let Repr::Dynamic(r) = &atom.0 {
+        HASH_MAP
+            .entry(r.1)
+            .or_insert_with(SmallVec::new)
+            .push(Share::<(SmolStr, Usize)>::downgrade(r));
+    }
+}
+
+/// 清理 `HASH_MAP`：摘除每个桶里已经失效的弱引用，桶变空后整条记录一并删除。
+#[inline]
+pub fn collect() {
+    HASH_MAP.retain(|_, bucket| {
+        bucket.retain(|w| w.strong_count() > 0);
+        !bucket.is_empty()
+    });
+}
+
+/// 返回当前动态池中所有存活 Atom 的一份快照，供应用层按模块文档建议的
+/// 思路、定期与自己的缓存做核对（检查引用计数来决定是否继续缓冲）。
+pub fn pool_snapshot() -> Vec<Atom> {
+    ATOM_MAP
+        .iter()
+        .map(|e| Atom(Repr::Dynamic(e.value().clone())))
+        .collect()
+}
+
+/// 强制回收动态池中已经没有外部引用、只被 `ATOM_MAP` 自己持有的条目。
+/// 和 `Drop`/`gc()` 的惰性回收不同，这里是一次性、立即生效的整理。
+pub fn shrink() {
+    // 开启 epoch_gc 时，Drop 只是把候选条目挂到挂起队列上，并没有真正减少
+    // ATOM_MAP 里那份 Share 的强引用计数（队列持有从 Atom 里移交过来的那一份）。在扫描强
+    // 引用计数之前必须先把这个队列排空，否则这些条目会被误判为"仍被外部
+    // 持有"而不会被回收，和下面这一遍扫描想要的"立即生效"语义矛盾。
+    #[cfg(feature = "epoch_gc")]
+    {
+        epoch_gc::gc();
+    }
+    ATOM_MAP.retain(|_, r| Share::<(SmolStr, Usize)>::strong_count(r) > 1);
+    #[cfg(feature = "lookup_by_hash")]
+    {
+        collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //use std::{time::Duration, thread};
+
+
+    use crate::*;
+    use pi_hash::XHashMap;
+
+    #[test]
+    fn test_atom1() {
+        let at3 = Atom::from("RES_GLTF_ACCESSOR_BUFFER_VIEW:app/scene_res/res/u3d_anim/eff_sz_chouka_daiji/eff_sz_chouka_daiji.gltf#Indices#19");
+        let at4 = Atom::from("RES_GLTF_ACCESSOR_BUFFER_VIEW:app/scene_res/res/u3d_anim/eff_sz_chouka_daiji/eff_sz_chouka_daiji.gltf#Indices#34");
+        println!("at3:{:?}, at4:{:?}", at3.str_hash(), at4.str_hash())
+    }
+
+    #[test]
+    fn test_atom() {
+        let at3 = Atom::from("afg");
+        assert_eq!(at3.as_str(), "afg");
+
+        let mut map = XHashMap::default();
+        let time = std::time::Instant::now();
+        for i in 0..1000000 {
+            map.insert(i.to_string(), i);
+        }
+        println!("insert map time:{:?}", std::time::Instant::now() - time);
+
+        let time = std::time::Instant::now();
+        let mut vec1 = vec![];
+        for i in 0..1000000 {
+            vec1.push(Atom::from(i.to_string()));
+        }
+        println!("atom from time:{:?}", std::time::Instant::now() - time);
+
+        let time = std::time::Instant::now();
+        let mut vec2 = vec![];
+        for i in 0..1000000 {
+            vec2.push(Atom::from(i.to_string()));
+        }
+        println!("atom look time:{:?}", std::time::Instant::now() - time);
+
+        let mut arr3 = Vec::new();
+        for i in 0..1000 {
+            arr3.push(Atom::from(i.to_string()));
+        }
+        let mut arr4 = Vec::new();
+        let time = std::time::Instant::now();
+        for i in 0..1000 {
+            for _ in 0..1000 {
+                arr4.push(Atom::from(arr3[i].as_str()));
+            }
+        }
+        println!("atom1 from time:{:?}", std::time::Instant::now() - time);
+        let mut arr5 = Vec::new();
+        let time = std::time::Instant::now();
+        for i in 0..1000 {
+            for _ in 0..1000 {
+                arr5.push(Share::new((arr3[i].as_str().to_string(), 5)));
+            }
+        }
+        println!("Share::new time:{:?}", std::time::Instant::now() - time);
+
+        let time = std::time::Instant::now();
+        for i in 0..1000 {
+            for _ in 0..1000 {
+                let _ = arr3[i].as_str();
+            }
+        }
+        println!("to_str time:{:?}", std::time::Instant::now() - time);
+
+        let time = std::time::Instant::now();
+        let xx = Share::new(1);
+        let w = Share::downgrade(&xx);
+        for _ in 0..1000000 {
+            let _ = w.upgrade();
+        }
+        println!("upgrade:{:?}", std::time::Instant::now() - time);
+
+        let time = std::time::Instant::now();
+        let xx = Share::new(1);
+        //let w = Share::downgrade(&xx);
+        for _ in 0..1000 {
+            for _ in 0..1000 {
+                let _a = xx.clone();
+            }
+        }
+        println!("clone: {:?}", std::time::Instant::now() - time);
+    }
+
+    #[test]
+    fn test_inline_atom() {
+        // 长度不超过 INLINE_CAP 的字符串不应经过 ATOM_MAP
+        let short = Atom::from("atom12");
+        assert_eq!(short.as_str(), "atom12");
+        assert!(!ATOM_MAP.contains_key("atom12"));
+
+        // 超过 INLINE_CAP 的字符串仍然走池化路径
+        let long = Atom::from("atom_long_enough");
+        assert_eq!(long.as_str(), "atom_long_enough");
+        assert!(ATOM_MAP.contains_key("atom_long_enough"));
+    }
+
+    #[test]
+    fn test_static_atom() {
+        let a = atom!("className");
+        assert!(a.is_static());
+        assert_eq!(a.as_str(), "className");
+        assert!(!ATOM_MAP.contains_key("className"));
+
+        // 未登记的字符串仍然走内联/动态路径，不会命中静态表
+        let b = Atom::from("not_a_static_atom_xyz");
+        assert!(!b.is_static());
+
+        // "viewBox" 正好是 INLINE_CAP 个字节，静态表要优先于内联检查，
+        // 否则它会一直被当成内联字符串，永远解析不出 Static 变体
+        assert_eq!("viewBox".len(), INLINE_CAP);
+        let c = atom!("viewBox");
+        assert!(c.is_static());
+        assert_eq!(c.as_str(), "viewBox");
+    }
+
+    #[test]
+    fn test_dynamic_atom_eq() {
+        let a = Atom::from("a_long_enough_dynamic_atom");
+        let b = Atom::from("a_long_enough_dynamic_atom");
+        // 两次 from 命中同一个池项，必然是同一个 Share
+        assert_eq!(a, b);
+        assert_ne!(a, Atom::from("another_long_enough_atom"));
+    }
+
+    #[test]
+    fn test_lookup_by_hash() {
+        let a = Atom::from("lookup_by_hash_sample_a");
+        let b = Atom::from("lookup_by_hash_sample_b");
+        store_weak_by_hash(a.clone());
+        store_weak_by_hash(b.clone());
+
+        assert_eq!(get_by_hash_str(a.str_hash(), "lookup_by_hash_sample_a"), Some(a.clone()));
+        assert_eq!(get_by_hash_str(a.str_hash(), "no_such_string"), None);
+        assert!(get_by_hash(a.str_hash()).iter().any(|at| *at == a));
+    }
+
+    #[cfg(feature = "epoch_gc")]
+    #[test]
+    fn test_epoch_gc() {
+        set_gc_threshold(1);
+        {
+            let _a = Atom::from("epoch_gc_sample_string");
+        }
+        // 以防阈值触发的自动回收还没跑完，再显式 flush 一次
+        gc();
+        assert!(!ATOM_MAP.contains_key("epoch_gc_sample_string"));
+    }
+
+    #[test]
+    fn test_intern_many_and_snapshot() {
+        // ATOM_MAP 是进程全局的，测试默认并发运行，其它用例随时会在其中
+        // 创建/释放原子，所以这里不能断言 pool_len() 的具体值，只能断言
+        // 这几个独有的字符串本身有没有出现/消失。
+        let atoms = Atom::intern_many(["bulk_atom_one", "bulk_atom_two", "bulk_atom_three"]);
+        assert_eq!(atoms[1].as_str(), "bulk_atom_two");
+        assert!(ATOM_MAP.contains_key("bulk_atom_one"));
+
+        let snapshot = pool_snapshot();
+        assert!(snapshot.iter().any(|a| a.as_str() == "bulk_atom_one"));
+
+        // 快照里的 Atom 也是对池项的强引用，必须一并释放，shrink() 才能回收
+        drop(snapshot);
+        drop(atoms);
+        shrink();
+        assert!(!ATOM_MAP.contains_key("bulk_atom_one"));
+        assert!(!ATOM_MAP.contains_key("bulk_atom_two"));
+        assert!(!ATOM_MAP.contains_key("bulk_atom_three"));
+    }
+
+    #[test]
+    fn test_rng() {
+        let _thread = std::thread::spawn(|| {
+            rng();
+            return;
+        });
+
+        // thread.join().unwrap();
+
+        rng();
+        return;
+    }
+    fn rng() {
+        let mut vec = vec![];
+        for _ in 0..1000000 {
+            //thread::sleep(Duration::from_millis(0));
+            let mut buf = [0u8; 4];
+            getrandom::getrandom(&mut buf).unwrap();
+            let r = unsafe { *(buf.as_ptr() as usize as *mut u32) };
+            if r % 4 == 0 {
+                vec.push(Atom::from(r.to_string()));
+            } else if r % 4 == 1 && vec.len() > 0 {
+                let c = vec[r as usize % vec.len()].clone();
+                vec.push(c);
+            } else {
+                if vec.len() > 0 {
+                    vec.swap_remove(r as usize % vec.len());
+                }
+            }
+        }
+    }
+}